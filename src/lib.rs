@@ -1,9 +1,13 @@
 #![feature(portable_simd)]
 
-use std::alloc::{Layout, alloc, dealloc};
-use std::simd::Simd;
+mod slice_cast;
+
+use std::alloc::{alloc, dealloc, Layout};
 use std::simd::f32x4;
-use std::simd::num::SimdFloat;
+use std::simd::i16x4;
+use std::simd::i32x4;
+use std::simd::num::{SimdFloat, SimdInt};
+use std::simd::Simd;
 
 unsafe extern "C" {
     unsafe fn console_log(ptr: *const u8, len: usize);
@@ -64,23 +68,380 @@ pub extern "C" fn custom_alloc(len: usize) -> *mut u8 {
     ptr
 }
 
+/// Target PCM layout for [`process_audio_simd`].
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleFormat {
+    /// Signed 16-bit, the crate's original output format.
+    S16 = 0,
+    /// Unsigned 16-bit, biased by `+32768` relative to `S16`.
+    U16 = 1,
+    /// Signed 32-bit.
+    S32 = 2,
+    /// Normalized `f32` passthrough (clamp only, no scaling).
+    F32 = 3,
+}
+
+impl SampleFormat {
+    fn from_u32(format: u32) -> Self {
+        match format {
+            1 => SampleFormat::U16,
+            2 => SampleFormat::S32,
+            3 => SampleFormat::F32,
+            _ => SampleFormat::S16,
+        }
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn process_audio_simd(
+    input_ptr: *const f32,
+    output_ptr: *mut u8,
+    len: usize,
+    format: u32,
+) {
+    const LANES: usize = 4;
+
+    let float_slice = match unsafe { slice_cast::as_f32_slice(input_ptr, len) } {
+        Ok(slice) => slice,
+        Err(_) => {
+            log("process_audio_simd: invalid input pointer/length");
+            return;
+        }
+    };
+    let format = SampleFormat::from_u32(format);
+
+    let min = Simd::splat(-1.0);
+    let max = Simd::splat(1.0);
+
+    let full_lanes = (len / LANES) * LANES;
+
+    match format {
+        SampleFormat::S16 => {
+            let int_slice = match unsafe { slice_cast::as_mut_slice(output_ptr as *mut i16, len) } {
+                Ok(slice) => slice,
+                Err(_) => {
+                    log("process_audio_simd: invalid output pointer/length");
+                    return;
+                }
+            };
+            let chunks = float_slice.chunks_exact(LANES);
+            let tail = chunks.remainder();
+
+            for (i, chunk) in chunks.enumerate() {
+                let input_chunk = f32x4::from_slice(chunk);
+                let clamped = input_chunk.simd_clamp(min, max);
+                let scaled = clamped * f32x4::splat(i16::MAX as f32);
+                let ints = scaled.cast::<i16>();
+
+                ints.copy_to_slice(&mut int_slice[i * LANES..(i + 1) * LANES]);
+            }
+
+            for (i, &sample) in tail.iter().enumerate() {
+                let clamped = sample.clamp(-1.0, 1.0);
+                int_slice[full_lanes + i] = (clamped * i16::MAX as f32) as i16;
+            }
+        }
+        SampleFormat::U16 => {
+            let uint_slice = match unsafe { slice_cast::as_mut_slice(output_ptr as *mut u16, len) }
+            {
+                Ok(slice) => slice,
+                Err(_) => {
+                    log("process_audio_simd: invalid output pointer/length");
+                    return;
+                }
+            };
+            let chunks = float_slice.chunks_exact(LANES);
+            let tail = chunks.remainder();
+
+            for (i, chunk) in chunks.enumerate() {
+                let input_chunk = f32x4::from_slice(chunk);
+                let clamped = input_chunk.simd_clamp(min, max);
+                let scaled = clamped * f32x4::splat(i16::MAX as f32);
+                let biased = scaled.cast::<i32>() + i32x4::splat(32768);
+                let uints = biased.cast::<u16>();
+
+                uints.copy_to_slice(&mut uint_slice[i * LANES..(i + 1) * LANES]);
+            }
+
+            for (i, &sample) in tail.iter().enumerate() {
+                let clamped = sample.clamp(-1.0, 1.0);
+                let scaled = (clamped * i16::MAX as f32) as i32 + 32768;
+                uint_slice[full_lanes + i] = scaled as u16;
+            }
+        }
+        SampleFormat::S32 => {
+            let int_slice = match unsafe { slice_cast::as_mut_slice(output_ptr as *mut i32, len) } {
+                Ok(slice) => slice,
+                Err(_) => {
+                    log("process_audio_simd: invalid output pointer/length");
+                    return;
+                }
+            };
+            let chunks = float_slice.chunks_exact(LANES);
+            let tail = chunks.remainder();
+
+            for (i, chunk) in chunks.enumerate() {
+                let input_chunk = f32x4::from_slice(chunk);
+                let clamped = input_chunk.simd_clamp(min, max);
+                let scaled = clamped * f32x4::splat(i32::MAX as f32);
+                let ints = scaled.cast::<i32>();
+
+                ints.copy_to_slice(&mut int_slice[i * LANES..(i + 1) * LANES]);
+            }
+
+            for (i, &sample) in tail.iter().enumerate() {
+                let clamped = sample.clamp(-1.0, 1.0);
+                int_slice[full_lanes + i] = (clamped * i32::MAX as f32) as i32;
+            }
+        }
+        SampleFormat::F32 => {
+            let float_out =
+                match unsafe { slice_cast::as_f32_slice_mut(output_ptr as *mut f32, len) } {
+                    Ok(slice) => slice,
+                    Err(_) => {
+                        log("process_audio_simd: invalid output pointer/length");
+                        return;
+                    }
+                };
+            let chunks = float_slice.chunks_exact(LANES);
+            let tail = chunks.remainder();
+
+            for (i, chunk) in chunks.enumerate() {
+                let input_chunk = f32x4::from_slice(chunk);
+                let clamped = input_chunk.simd_clamp(min, max);
+
+                clamped.copy_to_slice(&mut float_out[i * LANES..(i + 1) * LANES]);
+            }
+
+            for (i, &sample) in tail.iter().enumerate() {
+                float_out[full_lanes + i] = sample.clamp(-1.0, 1.0);
+            }
+        }
+    }
+}
+
+/// Splits an interleaved multi-channel buffer (`LRLRLR...`) into `channels`
+/// contiguous planes, so each channel can be fed into [`process_audio_simd`]
+/// independently.
 #[unsafe(no_mangle)]
-pub extern "C" fn process_audio_simd(input_ptr: *const f32, output_ptr: *mut i16, byte_len: usize) {
+pub extern "C" fn deinterleave_f32(
+    input_ptr: *const f32,
+    output_ptr: *mut f32,
+    frames: usize,
+    channels: usize,
+) {
     const LANES: usize = 4;
 
-    let float_slice = unsafe { std::slice::from_raw_parts(input_ptr, byte_len) };
+    let total = frames * channels;
+    let input = match unsafe { slice_cast::as_f32_slice(input_ptr, total) } {
+        Ok(slice) => slice,
+        Err(_) => {
+            log("deinterleave_f32: invalid input pointer/length");
+            return;
+        }
+    };
+    let output = match unsafe { slice_cast::as_f32_slice_mut(output_ptr, total) } {
+        Ok(slice) => slice,
+        Err(_) => {
+            log("deinterleave_f32: invalid output pointer/length");
+            return;
+        }
+    };
+
+    for channel in 0..channels {
+        let plane = &mut output[channel * frames..(channel + 1) * frames];
+        let full_frames = (frames / LANES) * LANES;
+
+        for base in (0..full_frames).step_by(LANES) {
+            let indices: Simd<usize, LANES> =
+                Simd::from_array(std::array::from_fn(|j| (base + j) * channels + channel));
+            let gathered = Simd::<f32, LANES>::gather_or_default(input, indices);
+            gathered.copy_to_slice(&mut plane[base..base + LANES]);
+        }
+
+        for frame in full_frames..frames {
+            plane[frame] = input[frame * channels + channel];
+        }
+    }
+}
+
+/// Inverse of [`deinterleave_f32`]: merges `channels` contiguous planes back
+/// into a single interleaved buffer (`LRLRLR...`).
+#[unsafe(no_mangle)]
+pub extern "C" fn interleave_f32(
+    input_ptr: *const f32,
+    output_ptr: *mut f32,
+    frames: usize,
+    channels: usize,
+) {
+    const LANES: usize = 4;
+
+    let total = frames * channels;
+    let input = match unsafe { slice_cast::as_f32_slice(input_ptr, total) } {
+        Ok(slice) => slice,
+        Err(_) => {
+            log("interleave_f32: invalid input pointer/length");
+            return;
+        }
+    };
+    let output = match unsafe { slice_cast::as_f32_slice_mut(output_ptr, total) } {
+        Ok(slice) => slice,
+        Err(_) => {
+            log("interleave_f32: invalid output pointer/length");
+            return;
+        }
+    };
+
+    for channel in 0..channels {
+        let plane = &input[channel * frames..(channel + 1) * frames];
+        let full_frames = (frames / LANES) * LANES;
+
+        for base in (0..full_frames).step_by(LANES) {
+            let values = f32x4::from_slice(&plane[base..base + LANES]);
+            let indices: Simd<usize, LANES> =
+                Simd::from_array(std::array::from_fn(|j| (base + j) * channels + channel));
+            values.scatter(output, indices);
+        }
+
+        for frame in full_frames..frames {
+            output[frame * channels + channel] = plane[frame];
+        }
+    }
+}
+
+/// Inverse of [`process_audio_simd`]: decodes signed 16-bit PCM back into
+/// normalized `f32` samples in `[-1.0, 1.0]`.
+#[unsafe(no_mangle)]
+pub extern "C" fn decode_audio_simd(input_ptr: *const i16, output_ptr: *mut f32, len: usize) {
+    const LANES: usize = 4;
+    const SCALE: f32 = 1.0 / 32768.0;
+
+    let int_slice = match unsafe { slice_cast::as_i16_slice(input_ptr, len) } {
+        Ok(slice) => slice,
+        Err(_) => {
+            log("decode_audio_simd: invalid input pointer/length");
+            return;
+        }
+    };
+    let float_slice = match unsafe { slice_cast::as_f32_slice_mut(output_ptr, len) } {
+        Ok(slice) => slice,
+        Err(_) => {
+            log("decode_audio_simd: invalid output pointer/length");
+            return;
+        }
+    };
+
+    let full_lanes = (len / LANES) * LANES;
+    let chunks = int_slice.chunks_exact(LANES);
+    let tail = chunks.remainder();
+
+    for (i, chunk) in chunks.enumerate() {
+        let input_chunk = i16x4::from_slice(chunk);
+        let widened = input_chunk.cast::<f32>();
+        let scaled = widened * f32x4::splat(SCALE);
+
+        scaled.copy_to_slice(&mut float_slice[i * LANES..(i + 1) * LANES]);
+    }
+
+    for (i, &s) in tail.iter().enumerate() {
+        float_slice[full_lanes + i] = s as f32 * SCALE;
+    }
+}
+
+/// Interpolation strategy used by [`resample_f32`] when mapping the input
+/// buffer onto a different sample rate.
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterpolationMode {
+    Nearest = 0,
+    Linear = 1,
+    Cosine = 2,
+    Cubic = 3,
+}
+
+impl InterpolationMode {
+    fn from_u32(mode: u32) -> Self {
+        match mode {
+            1 => InterpolationMode::Linear,
+            2 => InterpolationMode::Cosine,
+            3 => InterpolationMode::Cubic,
+            _ => InterpolationMode::Nearest,
+        }
+    }
+}
+
+// Возвращает sample по индексу, подменяя выход за границы крайними элементами.
+fn sample_at(input: &[f32], idx: isize) -> f32 {
+    let last = input.len() as isize - 1;
+    let clamped = idx.clamp(0, last);
+    input[clamped as usize]
+}
+
+/// Resamples `input` (length `input_len`) into `output` (length `output_len`)
+/// using the given [`InterpolationMode`].
+#[unsafe(no_mangle)]
+pub extern "C" fn resample_f32(
+    input_ptr: *const f32,
+    input_len: usize,
+    output_ptr: *mut f32,
+    output_len: usize,
+    mode: u32,
+) {
+    if output_len == 0 || input_len == 0 {
+        return;
+    }
+
+    let input = match unsafe { slice_cast::as_f32_slice(input_ptr, input_len) } {
+        Ok(slice) => slice,
+        Err(_) => {
+            log("resample_f32: invalid input pointer/length");
+            return;
+        }
+    };
+    let output = match unsafe { slice_cast::as_f32_slice_mut(output_ptr, output_len) } {
+        Ok(slice) => slice,
+        Err(_) => {
+            log("resample_f32: invalid output pointer/length");
+            return;
+        }
+    };
 
-    let int_slice = unsafe { std::slice::from_raw_parts_mut(output_ptr as *mut i16, byte_len) };
+    let mode = InterpolationMode::from_u32(mode);
+    let ratio = input_len as f32 / output_len as f32;
 
-    for (i, chunk) in float_slice.chunks_exact(LANES).enumerate() {
-        let input_chunk = f32x4::from_slice(chunk);
-        let min = Simd::splat(-1.0);
-        let max = Simd::splat(1.0);
-        let clamped = input_chunk.simd_clamp(min, max);
-        let scaled = clamped * f32x4::splat(i16::MAX as f32);
-        let ints = scaled.cast::<i16>();
+    for (i, out_sample) in output.iter_mut().enumerate() {
+        let pos = i as f32 * ratio;
+        let idx = pos.floor() as isize;
+        let mu = pos - idx as f32;
 
-        ints.copy_to_slice(&mut int_slice[i * LANES..(i + 1) * LANES]);
+        *out_sample = match mode {
+            InterpolationMode::Nearest => sample_at(input, pos.round() as isize),
+            InterpolationMode::Linear => {
+                let a = sample_at(input, idx);
+                let b = sample_at(input, idx + 1);
+                a + (b - a) * mu
+            }
+            InterpolationMode::Cosine => {
+                let a = sample_at(input, idx);
+                let b = sample_at(input, idx + 1);
+                let mu2 = (1.0 - (mu * std::f32::consts::PI).cos()) / 2.0;
+                a * (1.0 - mu2) + b * mu2
+            }
+            InterpolationMode::Cubic => {
+                let y0 = sample_at(input, idx - 1);
+                let y1 = sample_at(input, idx);
+                let y2 = sample_at(input, idx + 1);
+                let y3 = sample_at(input, idx + 2);
+
+                let a0 = y3 - y2 - y0 + y1;
+                let a1 = y0 - y1 - a0;
+                let a2 = y2 - y0;
+                let a3 = y1;
+
+                a0 * mu * mu * mu + a1 * mu * mu + a2 * mu + a3
+            }
+        };
     }
 }
 
@@ -136,7 +497,12 @@ mod tests {
         let input_ptr = INPUT_PTR.lock().unwrap().0.as_ptr();
         let output_ptr = OUTPUT_PTR.lock().unwrap().0.as_ptr();
 
-        let _ = process_audio_simd(input_ptr, output_ptr, LEN);
+        process_audio_simd(
+            input_ptr,
+            output_ptr as *mut u8,
+            LEN,
+            SampleFormat::S16 as u32,
+        );
 
         let result = unsafe { std::slice::from_raw_parts(output_ptr, LEN) };
         let mut ints = vec![];
@@ -144,4 +510,151 @@ mod tests {
 
         assert_eq!(ints, result)
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_process_audio_simd_u16() {
+        let mut output = vec![0u16; LEN];
+
+        process_audio_simd(
+            INPUT.as_ptr(),
+            output.as_mut_ptr() as *mut u8,
+            LEN,
+            SampleFormat::U16 as u32,
+        );
+
+        let expected: Vec<u16> = RESULT.iter().map(|&s| (s as i32 + 32768) as u16).collect();
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_process_audio_simd_s32() {
+        let mut output = vec![0i32; LEN];
+
+        process_audio_simd(
+            INPUT.as_ptr(),
+            output.as_mut_ptr() as *mut u8,
+            LEN,
+            SampleFormat::S32 as u32,
+        );
+
+        for (&sample, &input) in output.iter().zip(INPUT.iter()) {
+            let expected = (input.clamp(-1.0, 1.0) * i32::MAX as f32) as i32;
+            assert_eq!(sample, expected);
+        }
+    }
+
+    #[test]
+    fn test_process_audio_simd_f32_passthrough() {
+        let mut output = vec![0.0f32; LEN];
+
+        process_audio_simd(
+            INPUT.as_ptr(),
+            output.as_mut_ptr() as *mut u8,
+            LEN,
+            SampleFormat::F32 as u32,
+        );
+
+        assert_eq!(output, INPUT.to_vec());
+    }
+
+    #[test]
+    fn test_process_audio_simd_remainder() {
+        const TAIL_LEN: usize = 10;
+        let input: Vec<f32> = vec![0.1, -0.2, 0.3, -0.4, 0.5, -0.6, 0.7, -0.8, 0.9, -1.0];
+        let mut output = vec![0i16; TAIL_LEN];
+
+        process_audio_simd(
+            input.as_ptr(),
+            output.as_mut_ptr() as *mut u8,
+            TAIL_LEN,
+            SampleFormat::S16 as u32,
+        );
+
+        for (&sample, &expected_input) in output.iter().zip(input.iter()) {
+            let expected = (expected_input.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+            assert_eq!(sample, expected);
+        }
+    }
+
+    #[test]
+    fn test_resample_f32_nearest_identity() {
+        let input: Vec<f32> = INPUT.to_vec();
+        let mut output = vec![0.0f32; LEN];
+
+        resample_f32(
+            input.as_ptr(),
+            input.len(),
+            output.as_mut_ptr(),
+            output.len(),
+            InterpolationMode::Nearest as u32,
+        );
+
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn test_resample_f32_linear_upsample() {
+        let input: Vec<f32> = vec![0.0, 1.0];
+        let mut output = vec![0.0f32; 4];
+
+        resample_f32(
+            input.as_ptr(),
+            input.len(),
+            output.as_mut_ptr(),
+            output.len(),
+            InterpolationMode::Linear as u32,
+        );
+
+        assert_eq!(output, vec![0.0, 0.5, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_decode_audio_simd() {
+        let ints: Vec<i16> = RESULT.to_vec();
+        let mut floats = vec![0.0f32; LEN];
+
+        decode_audio_simd(ints.as_ptr(), floats.as_mut_ptr(), LEN);
+
+        for (decoded, original) in floats.iter().zip(INPUT.iter()) {
+            assert!((decoded - original).abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn test_decode_audio_simd_remainder() {
+        const TAIL_LEN: usize = 10;
+        let ints: Vec<i16> = vec![
+            1000, -2000, 3000, -4000, 5000, -6000, 7000, -8000, 9000, -10000,
+        ];
+        let mut floats = vec![0.0f32; TAIL_LEN];
+
+        decode_audio_simd(ints.as_ptr(), floats.as_mut_ptr(), TAIL_LEN);
+
+        for (&decoded, &original) in floats.iter().zip(ints.iter()) {
+            let expected = original as f32 * (1.0 / 32768.0);
+            assert!((decoded - expected).abs() < 0.0001);
+        }
+    }
+
+    #[test]
+    fn test_deinterleave_interleave_roundtrip() {
+        const FRAMES: usize = 6;
+        const CHANNELS: usize = 2;
+
+        let interleaved: Vec<f32> =
+            vec![0.1, 1.1, 0.2, 1.2, 0.3, 1.3, 0.4, 1.4, 0.5, 1.5, 0.6, 1.6];
+        let mut planar = vec![0.0f32; FRAMES * CHANNELS];
+
+        deinterleave_f32(interleaved.as_ptr(), planar.as_mut_ptr(), FRAMES, CHANNELS);
+
+        let left: Vec<f32> = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6];
+        let right: Vec<f32> = vec![1.1, 1.2, 1.3, 1.4, 1.5, 1.6];
+        assert_eq!(&planar[0..FRAMES], left.as_slice());
+        assert_eq!(&planar[FRAMES..2 * FRAMES], right.as_slice());
+
+        let mut roundtripped = vec![0.0f32; FRAMES * CHANNELS];
+        interleave_f32(planar.as_ptr(), roundtripped.as_mut_ptr(), FRAMES, CHANNELS);
+
+        assert_eq!(roundtripped, interleaved);
+    }
+}
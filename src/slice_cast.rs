@@ -0,0 +1,160 @@
+//! Checked reinterpretation of raw pointers as typed slices.
+//!
+//! Every exported processing function in this crate receives a raw pointer plus an
+//! *element* count (not a byte count) across the WASM boundary, then has to
+//! turn that into a Rust slice before it can do anything SIMD-friendly. This
+//! module centralizes that `unsafe` reconstruction behind helpers that check
+//! null/alignment/overflow once and return a `Result` instead of calling
+//! `slice::from_raw_parts[_mut]` directly at each call site. The checks rule
+//! out a few ways to misuse `from_raw_parts`, but not all of them — the
+//! helpers are still `unsafe`, and the caller remains responsible for `ptr`
+//! actually pointing at `len` live, unaliased elements of `T`.
+
+use std::mem::{align_of, size_of, size_of_val};
+use std::slice;
+
+/// Reason a raw pointer could not be reinterpreted as a typed slice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CastError {
+    NullPointer,
+    Misaligned,
+    LenOverflow,
+}
+
+/// Reconstructs a `&[T]` of `len` elements from `ptr`, checking for null,
+/// misalignment, and `len * size_of::<T>()` overflow before calling
+/// `slice::from_raw_parts`.
+///
+/// # Safety
+/// The caller must still ensure `ptr` is valid for reads of `len` elements
+/// of type `T` and that the memory isn't mutated for the returned lifetime.
+pub(crate) unsafe fn as_slice<'a, T>(ptr: *const T, len: usize) -> Result<&'a [T], CastError> {
+    if ptr.is_null() {
+        return Err(CastError::NullPointer);
+    }
+    if !(ptr as usize).is_multiple_of(align_of::<T>()) {
+        return Err(CastError::Misaligned);
+    }
+    if !fits_in_isize::<T>(len) {
+        return Err(CastError::LenOverflow);
+    }
+    Ok(unsafe { slice::from_raw_parts(ptr, len) })
+}
+
+/// Mutable counterpart of [`as_slice`].
+///
+/// # Safety
+/// The caller must still ensure `ptr` is valid for reads and writes of `len`
+/// elements of type `T` and that no other reference aliases that memory.
+pub(crate) unsafe fn as_mut_slice<'a, T>(
+    ptr: *mut T,
+    len: usize,
+) -> Result<&'a mut [T], CastError> {
+    if ptr.is_null() {
+        return Err(CastError::NullPointer);
+    }
+    if !(ptr as usize).is_multiple_of(align_of::<T>()) {
+        return Err(CastError::Misaligned);
+    }
+    if !fits_in_isize::<T>(len) {
+        return Err(CastError::LenOverflow);
+    }
+    Ok(unsafe { slice::from_raw_parts_mut(ptr, len) })
+}
+
+/// `true` if `len` elements of `T` fit both a `usize` byte count and the
+/// `isize::MAX` total-size limit that `slice::from_raw_parts[_mut]` requires.
+fn fits_in_isize<T>(len: usize) -> bool {
+    len.checked_mul(size_of::<T>())
+        .is_some_and(|n| n <= isize::MAX as usize)
+}
+
+/// Typed shorthand for [`as_slice`].
+///
+/// # Safety
+/// Same contract as [`as_slice`]: `ptr` must be valid for reads of `len`
+/// `f32`s and the memory must not be mutated for the returned lifetime. The
+/// alignment/overflow checks performed here do not establish pointer
+/// validity — that remains the caller's obligation.
+pub(crate) unsafe fn as_f32_slice<'a>(ptr: *const f32, len: usize) -> Result<&'a [f32], CastError> {
+    unsafe { as_slice(ptr, len) }
+}
+
+/// Typed shorthand for [`as_mut_slice`].
+///
+/// # Safety
+/// Same contract as [`as_mut_slice`]: `ptr` must be valid for reads and
+/// writes of `len` `f32`s and no other reference may alias that memory. The
+/// alignment/overflow checks performed here do not establish pointer
+/// validity — that remains the caller's obligation.
+pub(crate) unsafe fn as_f32_slice_mut<'a>(
+    ptr: *mut f32,
+    len: usize,
+) -> Result<&'a mut [f32], CastError> {
+    unsafe { as_mut_slice(ptr, len) }
+}
+
+/// Typed shorthand for [`as_slice`].
+///
+/// # Safety
+/// Same contract as [`as_slice`]: `ptr` must be valid for reads of `len`
+/// `i16`s and the memory must not be mutated for the returned lifetime. The
+/// alignment/overflow checks performed here do not establish pointer
+/// validity — that remains the caller's obligation.
+pub(crate) unsafe fn as_i16_slice<'a>(ptr: *const i16, len: usize) -> Result<&'a [i16], CastError> {
+    unsafe { as_slice(ptr, len) }
+}
+
+/// Reinterprets a typed slice as raw bytes, e.g. for logging a buffer's
+/// contents without a copy. Not yet called by an exported function, kept
+/// alongside `as_f32_slice`/`as_i16_slice` for parity and so it can be
+/// unit-tested in isolation.
+#[allow(dead_code)]
+pub(crate) fn as_bytes<T>(slice: &[T]) -> &[u8] {
+    unsafe { slice::from_raw_parts(slice.as_ptr() as *const u8, size_of_val(slice)) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_null_pointer() {
+        let ptr: *const f32 = std::ptr::null();
+        assert_eq!(unsafe { as_f32_slice(ptr, 4) }, Err(CastError::NullPointer));
+    }
+
+    #[test]
+    fn rejects_len_overflow() {
+        let value = 1.0f32;
+        let ptr = &value as *const f32;
+        assert_eq!(
+            unsafe { as_f32_slice(ptr, usize::MAX) },
+            Err(CastError::LenOverflow)
+        );
+    }
+
+    #[test]
+    fn rejects_len_past_isize_max() {
+        let value = 1.0f32;
+        let ptr = &value as *const f32;
+        let len = isize::MAX as usize / size_of::<f32>() + 1;
+        assert_eq!(
+            unsafe { as_f32_slice(ptr, len) },
+            Err(CastError::LenOverflow)
+        );
+    }
+
+    #[test]
+    fn accepts_valid_slice() {
+        let data = [1.0f32, 2.0, 3.0];
+        let slice = unsafe { as_f32_slice(data.as_ptr(), data.len()) }.unwrap();
+        assert_eq!(slice, &data);
+    }
+
+    #[test]
+    fn as_bytes_matches_element_count() {
+        let data = [1i16, 2, 3, 4];
+        assert_eq!(as_bytes(&data).len(), data.len() * size_of::<i16>());
+    }
+}